@@ -0,0 +1,193 @@
+//! Parsing and rolling for standard polyhedral dice expressions, e.g. `2d6 + 1d4 - 2`.
+//!
+//! This is a separate surface to the narrative Genesys dice handled by the rest of the
+//! crate: a `SignedElement` is either a group of polyhedral `Dice` or a flat numeric
+//! `Bonus`, and `parse_expression`/`roll_expression` parse and roll them independently of
+//! `parse_line`/`roll_pool`.
+
+use nom::{branch, character, combinator, multi, sequence};
+use rand::Rng;
+
+use crate::error::ParserError;
+use crate::{parse_count, run_parser, ParseResult};
+
+/// A group of standard polyhedral dice, e.g. two six-sided dice (`2d6`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dice {
+    /// How many dice should be rolled.
+    pub count: u32,
+    /// How many sides each die has.
+    pub sides: u32,
+}
+
+impl Dice {
+    /// Rolls this group of dice, summing each individual die's result.
+    ///
+    /// A die with zero sides can never come up, so it contributes nothing to the total,
+    /// rather than panicking.
+    #[must_use]
+    pub fn roll(&self, rng: &mut impl Rng) -> u32 {
+        if self.sides == 0 {
+            return 0;
+        }
+        (0..self.count).map(|_| rng.gen_range(1..=self.sides)).sum()
+    }
+}
+
+/// A single element of a polyhedral dice expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Element {
+    /// A group of polyhedral dice to roll.
+    Dice(Dice),
+    /// A flat numeric bonus.
+    Bonus(u32),
+}
+
+/// An `Element` together with the sign it contributes to the expression's total.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignedElement {
+    /// Adds the element's value to the total.
+    Positive(Element),
+    /// Subtracts the element's value from the total.
+    Negative(Element),
+}
+
+fn parse_dice_element(i: &str) -> ParseResult<'_, Element> {
+    combinator::map(
+        sequence::separated_pair(parse_count, character::complete::char('d'), parse_count),
+        |(count, sides)| Element::Dice(Dice { count, sides }),
+    )(i)
+}
+
+fn parse_bonus_element(i: &str) -> ParseResult<'_, Element> {
+    combinator::map(parse_count, Element::Bonus)(i)
+}
+
+fn parse_element(i: &str) -> ParseResult<'_, Element> {
+    branch::alt((parse_dice_element, parse_bonus_element))(i)
+}
+
+fn parse_signed_element(i: &str) -> ParseResult<'_, SignedElement> {
+    combinator::map(
+        sequence::pair(combinator::opt(character::complete::char('-')), parse_element),
+        |(sign, element)| {
+            if sign.is_some() {
+                SignedElement::Negative(element)
+            } else {
+                SignedElement::Positive(element)
+            }
+        },
+    )(i)
+}
+
+fn parse_signed_elements(i: &str) -> ParseResult<'_, Vec<SignedElement>> {
+    combinator::map(
+        sequence::pair(
+            parse_signed_element,
+            // Every subsequent element may have a leading "+", which is otherwise optional.
+            multi::many0(sequence::preceded(combinator::opt(character::complete::char('+')), parse_signed_element)),
+        ),
+        |(first, rest)| {
+            let mut elements = vec![first];
+            elements.extend(rest);
+            elements
+        },
+    )(i)
+}
+
+fn element_dice(signed_element: &SignedElement) -> Option<Dice> {
+    match signed_element {
+        SignedElement::Positive(Element::Dice(dice)) | SignedElement::Negative(Element::Dice(dice)) => Some(*dice),
+        SignedElement::Positive(Element::Bonus(_)) | SignedElement::Negative(Element::Bonus(_)) => None,
+    }
+}
+
+/// Takes a standard polyhedral dice expression (e.g. `2d6 + 1d4 - 2`) and returns its
+/// elements, each tagged with the sign it contributes to the total.
+///
+/// # Examples
+///
+/// ```
+/// use dice_command_parser::{basic::parse_expression, error::ParserError};
+///
+/// let input = "2d6 + 1d4 - 2";
+/// let expression = parse_expression(&input)?;
+/// # Ok::<(), ParserError>(())
+/// ```
+///
+/// # Errors
+/// This function can fail for the same reasons as `parse_line`, plus
+/// `ParserError::InvalidDiceSides` if a dice group names a die with zero sides (e.g. `2d0`).
+pub fn parse_expression(i: &str) -> Result<Vec<SignedElement>, ParserError> {
+    let elements = run_parser(i, parse_signed_elements)?;
+    if elements.iter().filter_map(element_dice).any(|dice| dice.sides == 0) {
+        return Err(ParserError::InvalidDiceSides { sides: 0 });
+    }
+    Ok(elements)
+}
+
+/// Rolls every element of `elements`, summing dice totals and flat bonuses (honouring each
+/// element's sign) into a single total.
+#[must_use]
+pub fn roll_expression(elements: &[SignedElement], rng: &mut impl Rng) -> i64 {
+    elements
+        .iter()
+        .map(|signed_element| {
+            let (sign, element) = match signed_element {
+                SignedElement::Positive(element) => (1, element),
+                SignedElement::Negative(element) => (-1, element),
+            };
+            let value: i64 = match element {
+                Element::Dice(dice) => i64::from(dice.roll(rng)),
+                Element::Bonus(bonus) => i64::from(*bonus),
+            };
+            sign * value
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!(
+            parse_expression("2d6 + 1d4 - 2"),
+            Ok(vec![
+                SignedElement::Positive(Element::Dice(Dice { count: 2, sides: 6 })),
+                SignedElement::Positive(Element::Dice(Dice { count: 1, sides: 4 })),
+                SignedElement::Negative(Element::Bonus(2)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_expression_single_bonus() {
+        assert_eq!(parse_expression("5"), Ok(vec![SignedElement::Positive(Element::Bonus(5))]));
+    }
+
+    #[test]
+    fn test_parse_expression_rejects_a_zero_sided_die() {
+        assert_eq!(parse_expression("2d0"), Err(ParserError::InvalidDiceSides { sides: 0 }));
+    }
+
+    #[test]
+    fn test_roll_of_a_zero_sided_die_is_zero_instead_of_panicking() {
+        let mut rng = rand::thread_rng();
+        let dice = Dice { count: 2, sides: 0 };
+        assert_eq!(dice.roll(&mut rng), 0);
+    }
+
+    #[test]
+    fn test_roll_expression_sums_dice_and_bonuses() {
+        let mut rng = rand::thread_rng();
+        let elements = vec![
+            SignedElement::Positive(Element::Dice(Dice { count: 2, sides: 6 })),
+            SignedElement::Negative(Element::Bonus(1)),
+        ];
+        // 2d6 is in [2, 12], minus the flat 1 bonus.
+        let total = roll_expression(&elements, &mut rng);
+        assert!((1..=11).contains(&total));
+    }
+}
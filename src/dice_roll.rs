@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::fmt;
+
 use crate::dice::Dice;
 
 
@@ -32,4 +35,209 @@ impl DiceRoll {
             number_of_dice_to_roll,
         }
     }
+
+    /// Renders this roll in its long form, e.g. `"proficiency proficiency proficiency"`.
+    #[must_use]
+    pub fn to_long_form(&self) -> String {
+        vec![long_name(&self.die); self.number_of_dice_to_roll as usize].join(" ")
+    }
+}
+
+impl fmt::Display for DiceRoll {
+    /// Renders this roll in its compact form, e.g. `"3y"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.number_of_dice_to_roll, compact_letter(&self.die))
+    }
+}
+
+// The die ordering a `DiceGroup` is rendered in once its counts have been merged.
+fn canonical_rank(die: &Dice) -> u8 {
+    match die {
+        Dice::Proficiency => 0,
+        Dice::Ability => 1,
+        Dice::Boost => 2,
+        Dice::Challenge => 3,
+        Dice::Difficulty => 4,
+        Dice::Setback => 5,
+        Dice::Force => 6,
+    }
+}
+
+fn compact_letter(die: &Dice) -> char {
+    match die {
+        Dice::Boost => 'b',
+        Dice::Ability => 'g',
+        Dice::Proficiency => 'y',
+        Dice::Difficulty => 'p',
+        Dice::Setback => 'k',
+        Dice::Challenge => 'r',
+        Dice::Force => 'w',
+    }
+}
+
+fn long_name(die: &Dice) -> &'static str {
+    match die {
+        Dice::Boost => "boost",
+        Dice::Ability => "ability",
+        Dice::Proficiency => "proficiency",
+        Dice::Difficulty => "difficulty",
+        Dice::Setback => "setback",
+        Dice::Challenge => "challenge",
+        Dice::Force => "force",
+    }
+}
+
+/// A single comma-separated group of `DiceRoll`s, merged by die type and rendered in a
+/// stable, canonical order (Proficiency, Ability, Boost, Challenge, Difficulty, Setback,
+/// Force) regardless of the order the dice were typed in.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiceGroup(Vec<DiceRoll>);
+
+impl std::ops::Deref for DiceGroup {
+    type Target = [DiceRoll];
+
+    fn deref(&self) -> &[DiceRoll] {
+        &self.0
+    }
+}
+
+impl IntoIterator for DiceGroup {
+    type Item = DiceRoll;
+    type IntoIter = std::vec::IntoIter<DiceRoll>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<DiceRoll> for DiceGroup {
+    /// Merges every `DiceRoll` with the same `die` into one, then orders the result
+    /// canonically.
+    fn from_iter<I: IntoIterator<Item = DiceRoll>>(iter: I) -> Self {
+        let mut dice_counts: HashMap<Dice, u32> = HashMap::new();
+        for roll in iter {
+            let count = dice_counts.entry(roll.die).or_insert(0);
+            *count += roll.number_of_dice_to_roll;
+        }
+
+        let mut rolls: Vec<DiceRoll> = dice_counts
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(die, count)| DiceRoll::new(die, count))
+            .collect();
+        rolls.sort_by_key(|roll| canonical_rank(&roll.die));
+
+        DiceGroup(rolls)
+    }
+}
+
+impl DiceGroup {
+    /// Renders this group in its long form, e.g. `"proficiency proficiency ability"`.
+    #[must_use]
+    pub fn to_long_form(&self) -> String {
+        self.0.iter().map(DiceRoll::to_long_form).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl fmt::Display for DiceGroup {
+    /// Renders this group in its compact form, e.g. `"3y2g1b"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for roll in &self.0 {
+            write!(f, "{roll}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A full parsed line: one or more comma-separated `DiceGroup`s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiceLine(Vec<DiceGroup>);
+
+impl std::ops::Deref for DiceLine {
+    type Target = [DiceGroup];
+
+    fn deref(&self) -> &[DiceGroup] {
+        &self.0
+    }
+}
+
+impl IntoIterator for DiceLine {
+    type Item = DiceGroup;
+    type IntoIter = std::vec::IntoIter<DiceGroup>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<DiceGroup> for DiceLine {
+    fn from_iter<I: IntoIterator<Item = DiceGroup>>(iter: I) -> Self {
+        DiceLine(iter.into_iter().collect())
+    }
+}
+
+impl DiceLine {
+    /// Renders this line in its long form, with groups separated by `", "`.
+    #[must_use]
+    pub fn to_long_form(&self) -> String {
+        self.0.iter().map(DiceGroup::to_long_form).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl fmt::Display for DiceLine {
+    /// Renders this line in its compact form, with groups separated by `","`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", groups.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dice_roll_display_is_compact() {
+        assert_eq!(DiceRoll::new(Dice::Proficiency, 3).to_string(), "3y");
+        assert_eq!(DiceRoll::new(Dice::Boost, 1).to_string(), "1b");
+    }
+
+    #[test]
+    fn test_dice_roll_long_form_repeats_the_name() {
+        assert_eq!(DiceRoll::new(Dice::Proficiency, 3).to_long_form(), "proficiency proficiency proficiency");
+    }
+
+    #[test]
+    fn test_dice_group_merges_and_orders_canonically() {
+        let group: DiceGroup = vec![
+            DiceRoll::new(Dice::Boost, 1),
+            DiceRoll::new(Dice::Proficiency, 2),
+            DiceRoll::new(Dice::Proficiency, 1),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(group.to_string(), "3y1b");
+    }
+
+    #[test]
+    fn test_dice_group_long_form_joins_rolls_with_a_space() {
+        let group: DiceGroup = vec![DiceRoll::new(Dice::Ability, 2), DiceRoll::new(Dice::Boost, 1)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(group.to_long_form(), "ability ability boost");
+    }
+
+    #[test]
+    fn test_dice_line_display_joins_groups_with_a_comma() {
+        let line: DiceLine = vec![
+            vec![DiceRoll::new(Dice::Ability, 2)].into_iter().collect(),
+            vec![DiceRoll::new(Dice::Boost, 1)].into_iter().collect(),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(line.to_string(), "2g,1b");
+    }
 }
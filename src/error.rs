@@ -0,0 +1,49 @@
+use thiserror::Error;
+
+/// Represents the ways in which parsing a dice command can fail.
+///
+/// Every variant that refers back into the input carries a byte `offset` into the original
+/// input the caller passed in, so a caller can underline the offending character(s).
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum ParserError {
+    /// An unrecognised token was found where a die, modifier, or variable was expected.
+    #[error("unknown token '{token}' at position {offset}")]
+    UnknownDieToken {
+        /// The offending token text.
+        token: String,
+        /// The byte offset of the token within the input.
+        offset: usize,
+    },
+
+    /// The input contained no dice, modifiers, or variables to parse.
+    #[error("the pool is empty")]
+    EmptyPool,
+
+    /// The parser succeeded but did not consume the entire input.
+    #[error("unexpected trailing input '{remaining}' at position {offset}")]
+    TrailingInput {
+        /// The unconsumed remainder of the input.
+        remaining: String,
+        /// The byte offset of the remainder within the input.
+        offset: usize,
+    },
+
+    /// A dice or modifier count failed to parse as a number, most likely because it overflowed.
+    #[error("invalid count: {source}")]
+    InvalidCount {
+        /// The underlying error returned by `str::parse`.
+        #[from]
+        source: std::num::ParseIntError,
+    },
+
+    /// A named pool variable was referenced but had no entry in the resolver's lookup.
+    #[error("no pool variable named '{0}' was found")]
+    VariableNotFound(String),
+
+    /// A polyhedral dice group named a die with zero sides, which can never be rolled.
+    #[error("a die must have at least 1 side, got {sides}")]
+    InvalidDiceSides {
+        /// The invalid side count that was parsed.
+        sides: u32,
+    },
+}
@@ -10,22 +10,133 @@
 use std::collections::HashMap;
 
 use nom::{branch, bytes, multi, character, combinator, sequence, Err};
+use nom::error::{ErrorKind, FromExternalError, ParseError};
 
-/// Provides access to the `DiceRoll` struct.
+/// Provides access to the `DiceRoll`, `DiceGroup`, and `DiceLine` structs.
 pub mod dice_roll;
-/// Provides access to the `ParserError` struct.
+/// Provides access to the `ParserError` enum.
 pub mod error;
 /// Provices access to the `Dice` enum.
 pub mod dice;
+/// Provides access to the `roll_pool` function and `PoolResult` struct.
+pub mod roll;
+/// Provides access to the `Modifier` enum and pool upgrade/downgrade functions.
+pub mod modifier;
+/// Provides access to the `PoolElement` enum and the `resolve` function.
+pub mod variable;
+/// Provides access to standard polyhedral (e.g. `2d6 + 3`) dice parsing and rolling.
+pub mod basic;
 
 
-use crate::dice_roll::{DiceRoll};
+use crate::dice_roll::{DiceGroup, DiceLine, DiceRoll};
 use crate::dice::Dice;
 use crate::error::ParserError;
+use crate::modifier::{Modifier, Side};
+use crate::variable::PoolElement;
 
+// The error nom's combinators accumulate internally while parsing a line. Unlike
+// `ParserError`, this is never shown to callers: `run_parser` translates it into a
+// `ParserError` once parsing has finished, using `input` to compute a byte offset into the
+// line the caller passed in (spaces included).
+#[derive(Debug, PartialEq)]
+pub(crate) struct InternalError<'a> {
+    input: &'a str,
+    kind: InternalErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+enum InternalErrorKind {
+    Nom(ErrorKind),
+    InvalidCount(std::num::ParseIntError),
+}
+
+impl<'a> ParseError<&'a str> for InternalError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        InternalError {
+            input,
+            kind: InternalErrorKind::Nom(kind),
+        }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> FromExternalError<&'a str, std::num::ParseIntError> for InternalError<'a> {
+    fn from_external_error(input: &'a str, _kind: ErrorKind, source: std::num::ParseIntError) -> Self {
+        InternalError {
+            input,
+            kind: InternalErrorKind::InvalidCount(source),
+        }
+    }
+}
+
+pub(crate) type ParseResult<'a, T> = nom::IResult<&'a str, T, InternalError<'a>>;
+
+// Computes the byte offset of `remaining` within `stripped`, relying on `remaining` always
+// being a subslice of `stripped` produced by nom's combinators.
+pub(crate) fn offset_of(stripped: &str, remaining: &str) -> usize {
+    remaining.as_ptr() as usize - stripped.as_ptr() as usize
+}
+
+// Maps a byte offset into `stripped` (the whitespace-stripped copy of `raw` that parsing
+// actually runs against) back to the corresponding byte offset in `raw`, so callers can
+// underline the offending character in the string they actually typed.
+pub(crate) fn offset_in_raw_input(raw: &str, stripped_offset: usize) -> usize {
+    let mut seen = 0;
+    for (index, ch) in raw.char_indices() {
+        if ch == ' ' {
+            continue;
+        }
+        if seen == stripped_offset {
+            return index;
+        }
+        seen += 1;
+    }
+    raw.len()
+}
+
+pub(crate) fn to_parser_error(raw: &str, stripped: &str, error: InternalError) -> ParserError {
+    let offset = offset_in_raw_input(raw, offset_of(stripped, error.input));
+    match error.kind {
+        InternalErrorKind::InvalidCount(source) => ParserError::InvalidCount { source },
+        InternalErrorKind::Nom(_) => ParserError::UnknownDieToken {
+            token: error.input.split(',').next().unwrap_or(error.input).to_string(),
+            offset,
+        },
+    }
+}
+
+// Shared by every public entry point: strips whitespace from `i`, rejects an empty pool, runs
+// `parser` against the stripped copy, and translates any trailing input or internal parse
+// error back into a `ParserError` with an offset into `i` itself.
+pub(crate) fn run_parser<T>(i: &str, parser: impl for<'a> FnOnce(&'a str) -> ParseResult<'a, T>) -> Result<T, ParserError> {
+    let whitespaceless: String = i.replace(' ', "");
+
+    if whitespaceless.trim().is_empty() {
+        return Err(ParserError::EmptyPool);
+    }
+
+    match parser(&whitespaceless) {
+        Ok((remaining, parsed)) => {
+            if !remaining.trim().is_empty() {
+                return Err(ParserError::TrailingInput {
+                    remaining: remaining.to_string(),
+                    offset: offset_in_raw_input(i, offset_of(&whitespaceless, remaining)),
+                });
+            }
+            Ok(parsed)
+        }
+        Err(Err::Error(e) | Err::Failure(e)) => Err(to_parser_error(i, &whitespaceless, e)),
+        Err(Err::Incomplete(_)) => {
+            unreachable!("every combinator used here is a `complete` combinator")
+        }
+    }
+}
 
 // boost or blue or b
-fn parse_dice_as_value(i: &str) -> nom::IResult<&str, Dice> {
+fn parse_dice_as_value(i: &str) -> ParseResult<'_, Dice> {
     branch::alt((
         combinator::value(Dice::Ability, branch::alt((bytes::complete::tag_no_case("green"), bytes::complete::tag_no_case("g"), bytes::complete::tag_no_case("ability"), bytes::complete::tag_no_case("abil")))),
         combinator::value(Dice::Challenge, branch::alt((bytes::complete::tag_no_case("challenge"), bytes::complete::tag_no_case("cha"), bytes::complete::tag_no_case("red"), bytes::complete::tag_no_case("r"), ))),
@@ -37,34 +148,96 @@ fn parse_dice_as_value(i: &str) -> nom::IResult<&str, Dice> {
     ))(i)
 }
 
-// Matches: 2g or ggbbfd
-fn parse_dice(i: &str) -> nom::IResult<&str, DiceRoll> {
-    let result = sequence::tuple((
-        combinator::opt(character::complete::digit1), parse_dice_as_value ))(i);
-    match result {
-        Ok((remaining, (number_of_dice, dice))) => Ok((
-            remaining,
-            DiceRoll::new(dice, number_of_dice.map_or(Ok(1), str::parse).unwrap()),
-        )),
-        Err(e) => Err(e),
+// A run of digits that does not fit in a `u32` is a hard failure rather than a recoverable
+// error, so that `opt`/`alt` do not swallow it and it surfaces as `ParserError::InvalidCount`.
+pub(crate) fn parse_count(i: &str) -> ParseResult<'_, u32> {
+    let (remaining, digits) = character::complete::digit1(i)?;
+    match digits.parse::<u32>() {
+        Ok(count) => Ok((remaining, count)),
+        Err(source) => Err(Err::Failure(InternalError::from_external_error(i, ErrorKind::MapRes, source))),
     }
 }
 
-fn parse_group(i: &str) -> nom::IResult<&str, Vec<DiceRoll>> {
-    let (remaining, rolls) = multi::many1(parse_dice)(i)?;
-    
+// Matches: 2g or ggbbfd
+fn parse_dice(i: &str) -> ParseResult<'_, DiceRoll> {
+    combinator::map(
+        sequence::tuple((combinator::opt(parse_count), parse_dice_as_value)),
+        |(number_of_dice, dice)| DiceRoll::new(dice, number_of_dice.unwrap_or(1)),
+    )(i)
+}
+
+// Matches a digit-prefixed die, e.g. `2g`, but not a bare die token. Used alongside
+// `parse_bare_identifier` in the variable-aware grammar, where a bare alphabetic run needs to
+// be classified as a whole (see `parse_bare_identifier`) rather than matched die-token-first.
+fn parse_counted_dice(i: &str) -> ParseResult<'_, DiceRoll> {
+    combinator::map(
+        sequence::pair(parse_count, parse_dice_as_value),
+        |(number_of_dice, dice)| DiceRoll::new(dice, number_of_dice),
+    )(i)
+}
+
+// Matches a pool-level upgrade or downgrade modifier. Lowercase `u`/`d` target the
+// ability side, uppercase `U`/`D` target the difficulty side, e.g. `u2` upgrades the
+// ability side twice and `D1` downgrades the difficulty side once.
+fn parse_modifier(i: &str) -> ParseResult<'_, Modifier> {
+    branch::alt((
+        combinator::map(
+            sequence::preceded(character::complete::char('u'), combinator::opt(parse_count)),
+            |n: Option<u32>| Modifier::Upgrade(n.unwrap_or(1), Side::Ability),
+        ),
+        combinator::map(
+            sequence::preceded(character::complete::char('d'), combinator::opt(parse_count)),
+            |n: Option<u32>| Modifier::Downgrade(n.unwrap_or(1), Side::Ability),
+        ),
+        combinator::map(
+            sequence::preceded(character::complete::char('U'), combinator::opt(parse_count)),
+            |n: Option<u32>| Modifier::Upgrade(n.unwrap_or(1), Side::Difficulty),
+        ),
+        combinator::map(
+            sequence::preceded(character::complete::char('D'), combinator::opt(parse_count)),
+            |n: Option<u32>| Modifier::Downgrade(n.unwrap_or(1), Side::Difficulty),
+        ),
+    ))(i)
+}
+
+enum GroupToken {
+    Dice(DiceRoll),
+    Modifier(Modifier),
+}
+
+fn parse_group_token(i: &str) -> ParseResult<'_, GroupToken> {
+    branch::alt((
+        combinator::map(parse_dice, GroupToken::Dice),
+        combinator::map(parse_modifier, GroupToken::Modifier),
+    ))(i)
+}
+
+fn parse_group(i: &str) -> ParseResult<'_, DiceGroup> {
+    let (remaining, tokens) = multi::many1(parse_group_token)(i)?;
+
     let mut dice_counts: HashMap<Dice, u32> = HashMap::new();
+    let mut modifiers: Vec<Modifier> = Vec::new();
 
-    rolls.into_iter().for_each(|roll| {
-        let group = dice_counts.entry(roll.die).or_insert(0);
-       *group += roll.number_of_dice_to_roll;
-    });
+    for token in tokens {
+        match token {
+            GroupToken::Dice(roll) => {
+                let count = dice_counts.entry(roll.die).or_insert(0);
+                *count += roll.number_of_dice_to_roll;
+            }
+            GroupToken::Modifier(m) => modifiers.push(m),
+        }
+    }
+
+    let mut rolls: Vec<DiceRoll> = dice_counts.into_iter().map(|(key, value)| DiceRoll::new(key, value)).collect();
+
+    for m in modifiers {
+        modifier::apply(&mut rolls, m);
+    }
 
-    let rolls = dice_counts.into_iter().map(|(key, value)| DiceRoll::new(key, value)).collect();
-    Ok((remaining, rolls))
+    Ok((remaining, rolls.into_iter().collect()))
 }
 
-fn parse_groups(i: &str) -> nom::IResult<&str, Vec<Vec<DiceRoll>>> {
+fn parse_groups(i: &str) -> ParseResult<'_, DiceLine> {
     let (remaining, (group_rolls, other_groups)) = sequence::tuple((
         parse_group,
         combinator::opt(sequence::tuple((
@@ -78,13 +251,13 @@ fn parse_groups(i: &str) -> nom::IResult<&str, Vec<Vec<DiceRoll>>> {
         None => 0,
     };
 
-    let mut rolls: Vec<Vec<DiceRoll>> = Vec::with_capacity(other_groups_size + 1);
-    rolls.push(group_rolls);
+    let mut groups: Vec<DiceGroup> = Vec::with_capacity(other_groups_size + 1);
+    groups.push(group_rolls);
     if other_groups.is_some() {
         let (_, other_groups_rolls) = other_groups.unwrap();
-        rolls.extend(other_groups_rolls);
+        groups.extend(other_groups_rolls);
     }
-    Ok((remaining, rolls))
+    Ok((remaining, groups.into_iter().collect()))
 }
 
 /// Takes a string of dice input and returns a `Result<DiceRoll, ParserError>`
@@ -105,30 +278,84 @@ fn parse_groups(i: &str) -> nom::IResult<&str, Vec<Vec<DiceRoll>>> {
 ///
 /// # Errors
 /// This function can fail when one of the following occurs
-/// 1. The line failed to parse.
-/// 2. An error occurred parsing the numbers provided. This will likely be an overflow or underflow error.
+/// 1. The input was empty (`ParserError::EmptyPool`).
+/// 2. The line failed to parse because of an unrecognised token (`ParserError::UnknownDieToken`).
+/// 3. The line parsed but left unconsumed input (`ParserError::TrailingInput`).
+/// 4. A dice or modifier count failed to parse as a number (`ParserError::InvalidCount`).
 ///
 /// For more information see `ParserError`.
-pub fn parse_line(i: &str) -> Result<Vec<Vec<DiceRoll>>, ParserError> {
-    let whitespaceless: String = i.replace(" ", "");
+pub fn parse_line(i: &str) -> Result<DiceLine, ParserError> {
+    run_parser(i, parse_groups)
+}
 
-    match parse_groups(&whitespaceless) {
-        Ok((remaining, dice_rolls)) => {
-            if !remaining.trim().is_empty() {
-                return Err(ParserError::ParseError(format!(
-                    "Expected remaining input to be empty, found: {0}",
-                    remaining
-                )));
-            }
-            return Ok(dice_rolls);
-        }
-        Err(Err::Error(e)) | Err(Err::Failure(e)) => {
-            return Err(ParserError::ParseError(format!("{0}", e)));
-        }
-        Err(Err::Incomplete(_)) => {
-            return Err(ParserError::Unknown);
-        }
+// Matches a named pool variable explicitly marked with `$`, e.g. `$reflexes`. The `$` forces
+// variable interpretation even when the name alone would name a die, e.g. `$force`.
+fn parse_explicit_variable(i: &str) -> ParseResult<'_, PoolElement> {
+    combinator::map(
+        sequence::preceded(character::complete::char('$'), character::complete::alpha1),
+        |name: &str| PoolElement::Variable(name.to_string()),
+    )(i)
+}
+
+// Matches an un-prefixed identifier, e.g. `combat` or `g`. The whole alphabetic run is taken
+// as one token first, so a variable name that happens to start with a die's letter (e.g.
+// `reflexes` starting with the Challenge die's `r`) is never split into a die plus a mangled
+// variable; only when the entire run exactly names a die (e.g. `g` or `proficiency` on its
+// own) is it parsed as that die instead of a variable.
+fn parse_bare_identifier(i: &str) -> ParseResult<'_, PoolElement> {
+    let (remaining, name) = character::complete::alpha1(i)?;
+    if let Ok(("", dice)) = parse_dice_as_value(name) {
+        return Ok((remaining, PoolElement::Dice(DiceRoll::new(dice, 1))));
+    }
+    Ok((remaining, PoolElement::Variable(name.to_string())))
+}
+
+fn parse_variable_group_token(i: &str) -> ParseResult<'_, PoolElement> {
+    branch::alt((
+        combinator::map(parse_counted_dice, PoolElement::Dice),
+        parse_explicit_variable,
+        parse_bare_identifier,
+    ))(i)
+}
+
+fn parse_variable_group(i: &str) -> ParseResult<'_, Vec<PoolElement>> {
+    multi::many1(parse_variable_group_token)(i)
+}
+
+fn parse_variable_groups(i: &str) -> ParseResult<'_, Vec<Vec<PoolElement>>> {
+    let (remaining, (group, other_groups)) = sequence::tuple((
+        parse_variable_group,
+        combinator::opt(sequence::tuple((character::complete::char(','), parse_variable_groups))),
+    ))(i)?;
+
+    let mut groups: Vec<Vec<PoolElement>> = Vec::new();
+    groups.push(group);
+    if let Some((_, other_groups)) = other_groups {
+        groups.extend(other_groups);
     }
+    Ok((remaining, groups))
+}
+
+/// Takes a string of dice input that may reference named pool variables (e.g. `$reflexes`
+/// or `combat`) in place of, or alongside, literal dice tokens.
+///
+/// The result still contains unresolved `PoolElement::Variable` references; pass it to
+/// `variable::resolve` along with a lookup of stored pools to get a `DiceLine`.
+///
+/// # Examples
+///
+/// ```
+/// use dice_command_parser::{parse_line_with_variables, error::ParserError};
+///
+/// let input = "$reflexes2g";
+/// let pool = parse_line_with_variables(&input)?;
+/// # Ok::<(), ParserError>(())
+/// ```
+///
+/// # Errors
+/// This function can fail for the same reasons as `parse_line`.
+pub fn parse_line_with_variables(i: &str) -> Result<Vec<Vec<PoolElement>>, ParserError> {
+    run_parser(i, parse_variable_groups)
 }
 
 #[cfg(test)]
@@ -268,8 +495,182 @@ mod tests {
 
     #[test]
     fn test_parse_group() {
-        assert_eq!(parse_group("6ryyy"), Ok(("", vec![DiceRoll::new(Dice::Challenge, 6), DiceRoll::new(Dice::Proficiency, 3)])));
-        assert_eq!(parse_group("d"), Ok(("", vec![DiceRoll::new(Dice::Difficulty, 1)])));
-        assert_eq!(parse_group("ddkb"), Ok(("", vec![DiceRoll::new(Dice::Difficulty, 2), DiceRoll::new(Dice::Setback, 1), DiceRoll::new(Dice::Boost, 1)])));
+        // The canonical ordering puts Proficiency before Challenge, regardless of typed order.
+        assert_eq!(
+            parse_group("6ryyy"),
+            Ok(("", vec![DiceRoll::new(Dice::Proficiency, 3), DiceRoll::new(Dice::Challenge, 6)].into_iter().collect()))
+        );
+        // "d" on its own is the downgrade modifier, not the Difficulty die (which has no bare "d" token).
+        assert_eq!(parse_group("d"), Ok(("", DiceGroup::default())));
+        assert_eq!(
+            parse_group("ddkb"),
+            Ok(("", vec![DiceRoll::new(Dice::Boost, 1), DiceRoll::new(Dice::Setback, 1)].into_iter().collect()))
+        );
+    }
+
+    #[test]
+    fn test_parse_modifier() {
+        assert_eq!(parse_modifier("u"), Ok(("", Modifier::Upgrade(1, Side::Ability))));
+        assert_eq!(parse_modifier("u2"), Ok(("", Modifier::Upgrade(2, Side::Ability))));
+        assert_eq!(parse_modifier("d"), Ok(("", Modifier::Downgrade(1, Side::Ability))));
+        assert_eq!(parse_modifier("d1"), Ok(("", Modifier::Downgrade(1, Side::Ability))));
+        assert_eq!(parse_modifier("U"), Ok(("", Modifier::Upgrade(1, Side::Difficulty))));
+        assert_eq!(parse_modifier("U2"), Ok(("", Modifier::Upgrade(2, Side::Difficulty))));
+        assert_eq!(parse_modifier("D"), Ok(("", Modifier::Downgrade(1, Side::Difficulty))));
+        assert_eq!(parse_modifier("D1"), Ok(("", Modifier::Downgrade(1, Side::Difficulty))));
+        assert!(parse_modifier("x1").is_err());
+    }
+
+    #[test]
+    fn test_parse_group_upgrades_ability_side() {
+        assert_eq!(
+            parse_group("gu"),
+            Ok(("", vec![DiceRoll::new(Dice::Proficiency, 1)].into_iter().collect()))
+        );
+    }
+
+    #[test]
+    fn test_parse_group_downgrade_with_no_dice_left_is_a_no_op() {
+        assert_eq!(parse_group("gd2"), Ok(("", DiceGroup::default())));
+    }
+
+    #[test]
+    fn test_parse_group_can_target_the_difficulty_side_independently_of_the_ability_side() {
+        // A mixed pool of two Ability dice, a Proficiency die, and a Difficulty die: lowercase
+        // "u" upgrades the ability side (one Ability becomes a Proficiency), while uppercase "U"
+        // instead upgrades the difficulty side (the Difficulty die becomes a Challenge die),
+        // regardless of which sides happen to be present in the pool.
+        assert_eq!(
+            parse_group("2g1pyu"),
+            Ok((
+                "",
+                vec![DiceRoll::new(Dice::Ability, 1), DiceRoll::new(Dice::Proficiency, 2), DiceRoll::new(Dice::Difficulty, 1)]
+                    .into_iter()
+                    .collect()
+            ))
+        );
+        assert_eq!(
+            parse_group("2g1pyU"),
+            Ok((
+                "",
+                vec![DiceRoll::new(Dice::Ability, 2), DiceRoll::new(Dice::Proficiency, 1), DiceRoll::new(Dice::Challenge, 1)]
+                    .into_iter()
+                    .collect()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_group_accepts_a_bare_name_sharing_a_die_prefix() {
+        // "reflexes" shares its leading letter with the "r" (Challenge) die token, but the
+        // whole alphabetic run is taken as one identifier, so it's never split into a die
+        // plus a mangled variable name.
+        assert_eq!(
+            parse_variable_group("reflexes"),
+            Ok(("", vec![PoolElement::Variable("reflexes".to_string())]))
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_requires_dollar_to_disambiguate_a_name_that_is_exactly_a_die_token() {
+        // "force" on its own exactly names the Force die, so the "$" sigil is needed to force
+        // variable interpretation.
+        assert_eq!(parse_variable_group("force"), Ok(("", vec![PoolElement::Dice(DiceRoll::new(Dice::Force, 1))])));
+        assert_eq!(
+            parse_variable_group("$force"),
+            Ok(("", vec![PoolElement::Variable("force".to_string())]))
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_group_accepts_bare_identifier() {
+        assert_eq!(
+            parse_variable_group("combat"),
+            Ok(("", vec![PoolElement::Variable("combat".to_string())]))
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_group_mixes_dice_and_variables() {
+        assert_eq!(
+            parse_variable_group("2g$combat"),
+            Ok((
+                "",
+                vec![
+                    PoolElement::Dice(DiceRoll::new(Dice::Ability, 2)),
+                    PoolElement::Variable("combat".to_string()),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_line_with_variables() {
+        assert_eq!(
+            parse_line_with_variables("$combat,2g"),
+            Ok(vec![
+                vec![PoolElement::Variable("combat".to_string())],
+                vec![PoolElement::Dice(DiceRoll::new(Dice::Ability, 2))],
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_line_empty_pool() {
+        assert_eq!(parse_line(""), Err(ParserError::EmptyPool));
+        assert_eq!(parse_line("   "), Err(ParserError::EmptyPool));
+    }
+
+    #[test]
+    fn test_parse_line_unknown_die_token() {
+        assert_eq!(
+            parse_line("*1"),
+            Err(ParserError::UnknownDieToken {
+                token: "*1".to_string(),
+                offset: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_line_trailing_input() {
+        assert_eq!(
+            parse_line("g,"),
+            Err(ParserError::TrailingInput {
+                remaining: ",".to_string(),
+                offset: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_line_error_offsets_are_reported_against_the_original_input_with_spaces() {
+        // The leading space is stripped before parsing, but the reported offset should still
+        // point at "*" in the string the caller actually typed, not in the stripped copy.
+        assert_eq!(
+            parse_line(" *1"),
+            Err(ParserError::UnknownDieToken {
+                token: "*1".to_string(),
+                offset: 1,
+            })
+        );
+        assert_eq!(
+            parse_line("difficulty difficulty ability xyz"),
+            Err(ParserError::TrailingInput {
+                remaining: "xyz".to_string(),
+                offset: 30,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_line_renders_groups_in_canonical_order_regardless_of_input_order() {
+        // Typed as boost-then-proficiency-then-ability, but displayed proficiency-ability-boost.
+        assert_eq!(parse_line("byg2g").unwrap().to_string(), "1y3g1b");
+    }
+
+    #[test]
+    fn test_parse_line_display_joins_groups_with_a_comma() {
+        assert_eq!(parse_line("2g,1b").unwrap().to_string(), "2g,1b");
     }
 }
@@ -0,0 +1,176 @@
+use crate::dice::Dice;
+use crate::dice_roll::DiceRoll;
+
+/// Which side of a pool a `Modifier` applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// The green/yellow Ability/Proficiency side.
+    Ability,
+    /// The purple/red Difficulty/Challenge side.
+    Difficulty,
+}
+
+/// A pool-level modifier that upgrades or downgrades a side of a dice pool,
+/// as opposed to adding or removing a fixed number of dice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    /// Upgrade the given side of the pool this many times.
+    Upgrade(u32, Side),
+    /// Downgrade the given side of the pool this many times.
+    Downgrade(u32, Side),
+}
+
+fn count_of(rolls: &[DiceRoll], die: &Dice) -> u32 {
+    rolls
+        .iter()
+        .find(|roll| &roll.die == die)
+        .map_or(0, |roll| roll.number_of_dice_to_roll)
+}
+
+fn set_count(rolls: &mut Vec<DiceRoll>, die: Dice, count: u32) {
+    if count == 0 {
+        rolls.retain(|roll| roll.die != die);
+        return;
+    }
+    if let Some(roll) = rolls.iter_mut().find(|roll| roll.die == die) {
+        roll.number_of_dice_to_roll = count;
+    } else {
+        rolls.push(DiceRoll::new(die, count));
+    }
+}
+
+/// Upgrades the ability side of the pool, converting one `Ability` die into a
+/// `Proficiency` die. If there are no `Ability` dice left to convert, a new
+/// `Ability` die is added instead.
+pub fn upgrade_ability(rolls: &mut Vec<DiceRoll>) {
+    let ability = count_of(rolls, &Dice::Ability);
+    if ability > 0 {
+        set_count(rolls, Dice::Ability, ability - 1);
+        set_count(rolls, Dice::Proficiency, count_of(rolls, &Dice::Proficiency) + 1);
+    } else {
+        set_count(rolls, Dice::Ability, 1);
+    }
+}
+
+/// Downgrades the ability side of the pool, converting one `Proficiency` die
+/// into an `Ability` die. If there is no `Proficiency` die to convert, an
+/// `Ability` die is removed instead.
+pub fn downgrade_ability(rolls: &mut Vec<DiceRoll>) {
+    let proficiency = count_of(rolls, &Dice::Proficiency);
+    if proficiency > 0 {
+        set_count(rolls, Dice::Proficiency, proficiency - 1);
+        set_count(rolls, Dice::Ability, count_of(rolls, &Dice::Ability) + 1);
+    } else {
+        let ability = count_of(rolls, &Dice::Ability);
+        if ability > 0 {
+            set_count(rolls, Dice::Ability, ability - 1);
+        }
+    }
+}
+
+/// Upgrades the difficulty side of the pool, converting one `Difficulty` die
+/// into a `Challenge` die. If there are no `Difficulty` dice left to convert,
+/// a new `Difficulty` die is added instead.
+pub fn upgrade_difficulty(rolls: &mut Vec<DiceRoll>) {
+    let difficulty = count_of(rolls, &Dice::Difficulty);
+    if difficulty > 0 {
+        set_count(rolls, Dice::Difficulty, difficulty - 1);
+        set_count(rolls, Dice::Challenge, count_of(rolls, &Dice::Challenge) + 1);
+    } else {
+        set_count(rolls, Dice::Difficulty, 1);
+    }
+}
+
+/// Downgrades the difficulty side of the pool, converting one `Challenge` die
+/// into a `Difficulty` die. If there is no `Challenge` die to convert, a
+/// `Difficulty` die is removed instead.
+pub fn downgrade_difficulty(rolls: &mut Vec<DiceRoll>) {
+    let challenge = count_of(rolls, &Dice::Challenge);
+    if challenge > 0 {
+        set_count(rolls, Dice::Challenge, challenge - 1);
+        set_count(rolls, Dice::Difficulty, count_of(rolls, &Dice::Difficulty) + 1);
+    } else {
+        let difficulty = count_of(rolls, &Dice::Difficulty);
+        if difficulty > 0 {
+            set_count(rolls, Dice::Difficulty, difficulty - 1);
+        }
+    }
+}
+
+/// Applies `modifier` to the pool the number of times it specifies, on the side it names.
+pub fn apply(rolls: &mut Vec<DiceRoll>, modifier: Modifier) {
+    match modifier {
+        Modifier::Upgrade(times, Side::Ability) => {
+            for _ in 0..times {
+                upgrade_ability(rolls);
+            }
+        }
+        Modifier::Upgrade(times, Side::Difficulty) => {
+            for _ in 0..times {
+                upgrade_difficulty(rolls);
+            }
+        }
+        Modifier::Downgrade(times, Side::Ability) => {
+            for _ in 0..times {
+                downgrade_ability(rolls);
+            }
+        }
+        Modifier::Downgrade(times, Side::Difficulty) => {
+            for _ in 0..times {
+                downgrade_difficulty(rolls);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_ability_converts_existing_die() {
+        let mut rolls = vec![DiceRoll::new(Dice::Ability, 2)];
+        upgrade_ability(&mut rolls);
+        assert_eq!(count_of(&rolls, &Dice::Ability), 1);
+        assert_eq!(count_of(&rolls, &Dice::Proficiency), 1);
+    }
+
+    #[test]
+    fn test_upgrade_ability_adds_die_when_none_remain() {
+        let mut rolls = vec![];
+        upgrade_ability(&mut rolls);
+        assert_eq!(count_of(&rolls, &Dice::Ability), 1);
+        assert_eq!(count_of(&rolls, &Dice::Proficiency), 0);
+    }
+
+    #[test]
+    fn test_downgrade_ability_converts_existing_proficiency() {
+        let mut rolls = vec![DiceRoll::new(Dice::Proficiency, 1)];
+        downgrade_ability(&mut rolls);
+        assert_eq!(count_of(&rolls, &Dice::Proficiency), 0);
+        assert_eq!(count_of(&rolls, &Dice::Ability), 1);
+    }
+
+    #[test]
+    fn test_downgrade_ability_removes_die_when_no_proficiency() {
+        let mut rolls = vec![DiceRoll::new(Dice::Ability, 1)];
+        downgrade_ability(&mut rolls);
+        assert_eq!(count_of(&rolls, &Dice::Ability), 0);
+    }
+
+    #[test]
+    fn test_apply_targets_the_ability_side_when_requested() {
+        let mut rolls = vec![DiceRoll::new(Dice::Ability, 1), DiceRoll::new(Dice::Difficulty, 1)];
+        apply(&mut rolls, Modifier::Upgrade(1, Side::Ability));
+        assert_eq!(count_of(&rolls, &Dice::Proficiency), 1);
+        assert_eq!(count_of(&rolls, &Dice::Difficulty), 1);
+    }
+
+    #[test]
+    fn test_apply_targets_the_difficulty_side_when_requested() {
+        let mut rolls = vec![DiceRoll::new(Dice::Ability, 1), DiceRoll::new(Dice::Difficulty, 1)];
+        apply(&mut rolls, Modifier::Upgrade(1, Side::Difficulty));
+        assert_eq!(count_of(&rolls, &Dice::Ability), 1);
+        assert_eq!(count_of(&rolls, &Dice::Challenge), 1);
+    }
+}
@@ -0,0 +1,278 @@
+use rand::Rng;
+
+use crate::dice::Dice;
+use crate::dice_roll::DiceRoll;
+
+/// A single narrative symbol that can appear on a die face.
+///
+/// `Triumph` and `Despair` are also counted as a `Success` and a `Failure`
+/// respectively when a pool is resolved, on top of being reported on their own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symbol {
+    /// A success, cancels out a `Failure`.
+    Success,
+    /// A failure, cancels out a `Success`.
+    Failure,
+    /// An advantage, cancels out a `Threat`.
+    Advantage,
+    /// A threat, cancels out an `Advantage`.
+    Threat,
+    /// A triumph. Also counts as a `Success`.
+    Triumph,
+    /// A despair. Also counts as a `Failure`.
+    Despair,
+    /// A light side point, rolled on a Force die.
+    LightSide,
+    /// A dark side point, rolled on a Force die.
+    DarkSide,
+}
+
+// Each entry is the list of symbols shown on that face, indexed from 0.
+const BOOST: [&[Symbol]; 6] = [
+    &[],
+    &[],
+    &[Symbol::Success],
+    &[Symbol::Advantage],
+    &[Symbol::Advantage, Symbol::Advantage],
+    &[Symbol::Success, Symbol::Advantage],
+];
+
+const SETBACK: [&[Symbol]; 6] = [
+    &[],
+    &[],
+    &[Symbol::Failure],
+    &[Symbol::Failure],
+    &[Symbol::Threat],
+    &[Symbol::Threat],
+];
+
+const ABILITY: [&[Symbol]; 8] = [
+    &[],
+    &[Symbol::Success],
+    &[Symbol::Success],
+    &[Symbol::Success, Symbol::Success],
+    &[Symbol::Advantage],
+    &[Symbol::Advantage],
+    &[Symbol::Success, Symbol::Advantage],
+    &[Symbol::Advantage, Symbol::Advantage],
+];
+
+const DIFFICULTY: [&[Symbol]; 8] = [
+    &[],
+    &[Symbol::Failure],
+    &[Symbol::Failure, Symbol::Failure],
+    &[Symbol::Threat],
+    &[Symbol::Threat],
+    &[Symbol::Threat],
+    &[Symbol::Threat, Symbol::Threat],
+    &[Symbol::Failure, Symbol::Threat],
+];
+
+const PROFICIENCY: [&[Symbol]; 12] = [
+    &[],
+    &[Symbol::Success],
+    &[Symbol::Success],
+    &[Symbol::Success, Symbol::Success],
+    &[Symbol::Success, Symbol::Success],
+    &[Symbol::Advantage],
+    &[Symbol::Success, Symbol::Advantage],
+    &[Symbol::Success, Symbol::Advantage],
+    &[Symbol::Success, Symbol::Advantage],
+    &[Symbol::Advantage, Symbol::Advantage],
+    &[Symbol::Advantage, Symbol::Advantage],
+    &[Symbol::Triumph],
+];
+
+const CHALLENGE: [&[Symbol]; 12] = [
+    &[],
+    &[Symbol::Failure],
+    &[Symbol::Failure],
+    &[Symbol::Failure, Symbol::Failure],
+    &[Symbol::Failure, Symbol::Failure],
+    &[Symbol::Threat],
+    &[Symbol::Threat],
+    &[Symbol::Failure, Symbol::Threat],
+    &[Symbol::Failure, Symbol::Threat],
+    &[Symbol::Threat, Symbol::Threat],
+    &[Symbol::Threat, Symbol::Threat],
+    &[Symbol::Despair],
+];
+
+const FORCE: [&[Symbol]; 12] = [
+    &[Symbol::DarkSide],
+    &[Symbol::DarkSide],
+    &[Symbol::DarkSide],
+    &[Symbol::DarkSide],
+    &[Symbol::DarkSide],
+    &[Symbol::DarkSide, Symbol::DarkSide],
+    &[Symbol::LightSide],
+    &[Symbol::LightSide],
+    &[Symbol::LightSide],
+    &[Symbol::LightSide, Symbol::LightSide],
+    &[Symbol::LightSide, Symbol::LightSide],
+    &[Symbol::LightSide, Symbol::LightSide],
+];
+
+fn face_symbols(die: &Dice, face_index: usize) -> &'static [Symbol] {
+    match die {
+        Dice::Boost => BOOST[face_index],
+        Dice::Setback => SETBACK[face_index],
+        Dice::Ability => ABILITY[face_index],
+        Dice::Difficulty => DIFFICULTY[face_index],
+        Dice::Proficiency => PROFICIENCY[face_index],
+        Dice::Challenge => CHALLENGE[face_index],
+        Dice::Force => FORCE[face_index],
+    }
+}
+
+fn sides(die: &Dice) -> usize {
+    match die {
+        Dice::Boost | Dice::Setback => 6,
+        Dice::Ability | Dice::Difficulty => 8,
+        Dice::Proficiency | Dice::Challenge | Dice::Force => 12,
+    }
+}
+
+/// The net, resolved outcome of rolling a pool of narrative dice.
+///
+/// Successes are cancelled against failures, and advantages against threats,
+/// one-for-one. A `Triumph` counts as a net success (and a `Despair` as a
+/// net failure) on top of being reported in its own right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolResult {
+    /// Net successes remaining after cancelling against failures.
+    pub successes: u32,
+    /// Net failures remaining after cancelling against successes.
+    pub failures: u32,
+    /// Net advantage remaining after cancelling against threat.
+    pub advantage: u32,
+    /// Net threat remaining after cancelling against advantage.
+    pub threat: u32,
+    /// The number of triumphs rolled.
+    pub triumphs: u32,
+    /// The number of despairs rolled.
+    pub despairs: u32,
+    /// The number of light side Force points rolled.
+    pub light_side: u32,
+    /// The number of dark side Force points rolled.
+    pub dark_side: u32,
+}
+
+impl DiceRoll {
+    /// Rolls this `DiceRoll`, returning the symbols shown on every die rolled.
+    #[must_use]
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        for _ in 0..self.number_of_dice_to_roll {
+            let face_index = rng.gen_range(0..sides(&self.die));
+            symbols.extend_from_slice(face_symbols(&self.die, face_index));
+        }
+        symbols
+    }
+}
+
+// Cancels successes against failures and advantage against threat, one-for-one, counting a
+// triumph as an extra success and a despair as an extra failure along the way.
+fn resolve(symbols: impl IntoIterator<Item = Symbol>) -> PoolResult {
+    let mut successes: i64 = 0;
+    let mut advantage: i64 = 0;
+    let mut triumphs = 0;
+    let mut despairs = 0;
+    let mut light_side = 0;
+    let mut dark_side = 0;
+
+    for symbol in symbols {
+        match symbol {
+            Symbol::Success => successes += 1,
+            Symbol::Failure => successes -= 1,
+            Symbol::Advantage => advantage += 1,
+            Symbol::Threat => advantage -= 1,
+            Symbol::Triumph => {
+                triumphs += 1;
+                successes += 1;
+            }
+            Symbol::Despair => {
+                despairs += 1;
+                successes -= 1;
+            }
+            Symbol::LightSide => light_side += 1,
+            Symbol::DarkSide => dark_side += 1,
+        }
+    }
+
+    PoolResult {
+        successes: u32::try_from(successes.max(0)).unwrap_or(0),
+        failures: u32::try_from((-successes).max(0)).unwrap_or(0),
+        advantage: u32::try_from(advantage.max(0)).unwrap_or(0),
+        threat: u32::try_from((-advantage).max(0)).unwrap_or(0),
+        triumphs,
+        despairs,
+        light_side,
+        dark_side,
+    }
+}
+
+/// Rolls every `DiceRoll` in `rolls` and resolves the narrative symbols into a `PoolResult`.
+#[must_use]
+pub fn roll_pool(rolls: &[DiceRoll], rng: &mut impl Rng) -> PoolResult {
+    let mut symbols = Vec::new();
+    for roll in rolls {
+        symbols.extend(roll.roll(rng));
+    }
+    resolve(symbols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_cancels_successes_and_failures() {
+        let result = resolve(vec![Symbol::Success, Symbol::Failure]);
+        assert_eq!(result.successes, 0);
+        assert_eq!(result.failures, 0);
+    }
+
+    #[test]
+    fn test_resolve_triumph_counts_as_success() {
+        let result = resolve(vec![Symbol::Triumph]);
+        assert_eq!(result.successes, 1);
+        assert_eq!(result.triumphs, 1);
+    }
+
+    #[test]
+    fn test_resolve_despair_counts_as_failure() {
+        let result = resolve(vec![Symbol::Despair]);
+        assert_eq!(result.failures, 1);
+        assert_eq!(result.despairs, 1);
+    }
+
+    #[test]
+    fn test_resolve_force_points_are_not_cancelled_against_each_other() {
+        let result = resolve(vec![Symbol::DarkSide, Symbol::LightSide]);
+        assert_eq!(result.dark_side, 1);
+        assert_eq!(result.light_side, 1);
+    }
+
+    #[test]
+    fn test_force_die_has_six_dark_and_six_light_faces() {
+        let dark_faces = FORCE.iter().filter(|face| face.contains(&Symbol::DarkSide)).count();
+        let light_faces = FORCE.iter().filter(|face| face.contains(&Symbol::LightSide)).count();
+        assert_eq!(dark_faces, 6);
+        assert_eq!(light_faces, 6);
+    }
+
+    #[test]
+    fn test_roll_pool_is_bounded_by_the_dice_rolled() {
+        let mut rng = rand::thread_rng();
+        // A single Proficiency die can show at most two successes (or one triumph) and at
+        // most two advantage, and never any failure, threat, or despair.
+        let rolls = vec![DiceRoll::new(Dice::Proficiency, 1)];
+        let result = roll_pool(&rolls, &mut rng);
+        assert!(result.successes <= 2);
+        assert!(result.advantage <= 2);
+        assert_eq!(result.failures, 0);
+        assert_eq!(result.threat, 0);
+        assert_eq!(result.despairs, 0);
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crate::dice::Dice;
+use crate::dice_roll::{DiceGroup, DiceLine, DiceRoll};
+use crate::error::ParserError;
+
+/// A single parsed element of a pool that may still contain unresolved
+/// references to named pool variables.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PoolElement {
+    /// A literal die to roll.
+    Dice(DiceRoll),
+    /// A named reference to another pool, substituted in by `resolve`.
+    Variable(String),
+}
+
+/// Substitutes every `PoolElement::Variable` in `rolls` with the pool it names in
+/// `lookup`, merging die counts together the same way a literal group does.
+///
+/// # Errors
+/// Returns `ParserError::VariableNotFound` if a referenced name has no entry in `lookup`.
+pub fn resolve<S: BuildHasher>(
+    rolls: &[Vec<PoolElement>],
+    lookup: &HashMap<String, Vec<DiceRoll>, S>,
+) -> Result<DiceLine, ParserError> {
+    rolls.iter().map(|group| resolve_group(group, lookup)).collect()
+}
+
+fn resolve_group<S: BuildHasher>(
+    group: &[PoolElement],
+    lookup: &HashMap<String, Vec<DiceRoll>, S>,
+) -> Result<DiceGroup, ParserError> {
+    let mut dice_counts: HashMap<Dice, u32> = HashMap::new();
+
+    for element in group {
+        let rolls: Vec<DiceRoll> = match element {
+            PoolElement::Dice(roll) => vec![roll.clone()],
+            PoolElement::Variable(name) => lookup
+                .get(name)
+                .cloned()
+                .ok_or_else(|| ParserError::VariableNotFound(name.clone()))?,
+        };
+
+        for roll in rolls {
+            let count = dice_counts.entry(roll.die).or_insert(0);
+            *count += roll.number_of_dice_to_roll;
+        }
+    }
+
+    Ok(dice_counts
+        .into_iter()
+        .map(|(key, value)| DiceRoll::new(key, value))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_substitutes_variable() {
+        let rolls = vec![vec![PoolElement::Variable("reflexes".to_string())]];
+        let mut lookup = HashMap::new();
+        lookup.insert("reflexes".to_string(), vec![DiceRoll::new(Dice::Ability, 2)]);
+
+        assert_eq!(
+            resolve(&rolls, &lookup),
+            Ok(vec![vec![DiceRoll::new(Dice::Ability, 2)].into_iter().collect()].into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn test_resolve_merges_literal_and_variable_dice() {
+        let rolls = vec![vec![
+            PoolElement::Dice(DiceRoll::new(Dice::Ability, 1)),
+            PoolElement::Variable("combat".to_string()),
+        ]];
+        let mut lookup = HashMap::new();
+        lookup.insert("combat".to_string(), vec![DiceRoll::new(Dice::Ability, 1)]);
+
+        assert_eq!(
+            resolve(&rolls, &lookup),
+            Ok(vec![vec![DiceRoll::new(Dice::Ability, 2)].into_iter().collect()].into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn test_resolve_returns_variable_not_found() {
+        let rolls = vec![vec![PoolElement::Variable("missing".to_string())]];
+        let lookup = HashMap::new();
+
+        assert_eq!(
+            resolve(&rolls, &lookup),
+            Err(ParserError::VariableNotFound("missing".to_string()))
+        );
+    }
+}